@@ -1,78 +1,437 @@
 use core::fmt;
-use rand::Rng;
-use std::collections::HashSet;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// The wasm-bindgen-facing implementation, built around a flat cell array
+// instead of this module's `HashSet`/`HashMap` position sets. Pulls in the
+// `wasm-bindgen` crate, which the native build below doesn't otherwise need,
+// so it's opt-in behind its own feature rather than always-on.
+#[cfg(feature = "wasm")]
+pub mod minesweeper;
 
 pub type Position = (usize, usize);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Minesweeper {
     width: usize,
     height: usize,
-    mines: HashSet<Position>,
+    mine_count: usize,
+    // `None` until the first `reveal_cell`, which seeds the mines while
+    // excluding the clicked cell (and ideally its neighbors) so the first
+    // click can never lose.
+    mines: Option<HashSet<Position>>,
+    // Each non-mine cell's adjacent mine count, cached once by
+    // `recompute_mine_counts` when `mines` is seeded, so `count_mines` is
+    // an O(1) lookup instead of a neighbor walk on every call (`Display`
+    // re-reads it for every revealed cell on every render). Derived from
+    // `mines`, not part of a persisted game's identity (and `Position` keys
+    // can't round-trip through JSON maps anyway), so it's excluded from
+    // (de)serialization and rebuilt on load.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    mine_counts: HashMap<Position, u8>,
     open_cells: HashSet<Position>,
     flagged_cells: HashSet<Position>,
+    state: GameState,
+    // Seeds `create_mine_positions`. A saved game should be resumable by
+    // anyone, not tied to the RNG state of whoever paused it, so this is
+    // skipped on save and reseeded from entropy on load.
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip, default = "StdRng::from_entropy")
+    )]
+    rng: StdRng,
 }
 
 pub enum RevealResult {
     Mine,
     MineCount(u8),
+    GameOver,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameState {
+    Playing,
+    Won,
+    Lost,
+}
+
+// A single player action, as recorded by a `Replay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Move {
+    Reveal(Position),
+    ToggleFlag(Position),
+    Chord(Position),
+}
+
+// Records the moves played against a seeded board and reconstructs board
+// snapshots on demand, so a game can be stepped through move by move.
+//
+// Rewinding a `Minesweeper` isn't possible directly (its state lives in
+// mutating `HashSet`s), so every step re-derives the board by replaying
+// moves `0..current_index` from the seed.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    width: usize,
+    height: usize,
+    mine_count: usize,
+    seed: u64,
+    moves: Vec<Move>,
+    current_index: usize,
+    board: Minesweeper,
+}
+
+impl Replay {
+    pub fn new(width: usize, height: usize, mine_count: usize, seed: u64) -> Replay {
+        Replay {
+            width,
+            height,
+            mine_count,
+            seed,
+            moves: Vec::new(),
+            current_index: 0,
+            board: Minesweeper::new_seeded(width, height, mine_count, seed),
+        }
+    }
+
+    // Records `mv` as the next move, discarding any moves after the
+    // current position, and steps forward onto it.
+    pub fn record(&mut self, mv: Move) -> &Minesweeper {
+        self.moves.truncate(self.current_index);
+        self.moves.push(mv);
+        self.step_forward()
+    }
+
+    pub fn step_forward(&mut self) -> &Minesweeper {
+        if self.current_index < self.moves.len() {
+            self.current_index += 1;
+            self.board = self.board_at(self.current_index);
+        }
+        &self.board
+    }
+
+    pub fn step_back(&mut self) -> &Minesweeper {
+        if self.current_index > 0 {
+            self.current_index -= 1;
+            self.board = self.board_at(self.current_index);
+        }
+        &self.board
+    }
+
+    pub fn board(&self) -> &Minesweeper {
+        &self.board
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current_index
+    }
+
+    // Replays moves `0..index` from the seed to rebuild the board as it
+    // stood at that point.
+    fn board_at(&self, index: usize) -> Minesweeper {
+        let mut board = Minesweeper::new_seeded(self.width, self.height, self.mine_count, self.seed);
+        for mv in &self.moves[..index] {
+            match *mv {
+                Move::Reveal(p) => {
+                    board.reveal_cell(p);
+                }
+                Move::ToggleFlag(p) => {
+                    board.flag_cell(p);
+                }
+                Move::Chord(p) => {
+                    board.chord(p);
+                }
+            }
+        }
+        board
+    }
+}
+
+const MAX_NEIGHBORS: usize = 8;
+
+// A fixed-capacity, allocation-free set of up to 8 neighbor positions.
+// `get_neighbors_pos` used to return an iterator built from a nested
+// `flat_map`/`filter` closure chain, which `count_mines` then re-walked on
+// every call; a cell has at most 8 neighbors, so a stack array sized for
+// that is enough.
+struct NeighborPositions {
+    positions: [Position; MAX_NEIGHBORS],
+    len: usize,
+}
+
+impl NeighborPositions {
+    fn new() -> NeighborPositions {
+        NeighborPositions {
+            positions: [(0, 0); MAX_NEIGHBORS],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, pos: Position) {
+        self.positions[self.len] = pos;
+        self.len += 1;
+    }
+
+    fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        self.positions[..self.len].iter().copied()
+    }
 }
 
 impl Minesweeper {
     pub fn new(width: usize, height: usize, mine_count: usize) -> Minesweeper {
+        Self::with_rng(width, height, mine_count, StdRng::from_entropy())
+    }
+
+    // Like `new`, but drives mine placement from a seeded PRNG instead of
+    // system entropy, so the resulting board is reproducible from `seed`.
+    pub fn new_seeded(width: usize, height: usize, mine_count: usize, seed: u64) -> Minesweeper {
+        Self::with_rng(width, height, mine_count, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(width: usize, height: usize, mine_count: usize, rng: StdRng) -> Minesweeper {
         Minesweeper {
             width,
             height,
-            mines: {
-                let mut mines = HashSet::new();
-                while mines.len() < mine_count {
-                    let rand_width = rand::thread_rng().gen_range(0..width);
-                    let rand_height = rand::thread_rng().gen_range(0..height);
+            mine_count,
+            mines: None,
+            mine_counts: HashMap::new(),
+            open_cells: HashSet::new(),
+            flagged_cells: HashSet::new(),
+            state: GameState::Playing,
+            rng,
+        }
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    // Snapshots the full game — dimensions, mine layout, opened cells,
+    // flags and state — so it can be persisted and restored later.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Minesweeper> {
+        let mut ms: Minesweeper = serde_json::from_str(json)?;
+        ms.recompute_mine_counts();
+        Ok(ms)
+    }
+
+    fn create_mine_positions(
+        rng: &mut impl Rng,
+        width: usize,
+        height: usize,
+        mine_count: usize,
+        exclude: &HashSet<Position>,
+    ) -> HashSet<Position> {
+        // `mine_count` can't exceed the cells left once `exclude` (the
+        // first click, and ideally its neighbors) is carved out, or the
+        // insert loop below never reaches its target and spins forever.
+        let placeable = (width * height).saturating_sub(exclude.len());
+        let mine_count = mine_count.min(placeable);
+
+        let mut mines = HashSet::new();
+        while mines.len() < mine_count {
+            let rand_width = rng.gen_range(0..width);
+            let rand_height = rng.gen_range(0..height);
+            let pos = (rand_width, rand_height);
+
+            if !exclude.contains(&pos) {
+                mines.insert(pos);
+            }
+        }
+
+        mines
+    }
+
+    // Seeds the mine layout on first use, excluding `p` and, if there is
+    // enough room left on the board, its neighbors too.
+    fn ensure_seeded(&mut self, p: Position) {
+        if self.mines.is_some() {
+            return;
+        }
+
+        let with_neighbors: HashSet<Position> = self
+            .get_neighbors_pos(p)
+            .iter()
+            .chain(std::iter::once(p))
+            .collect();
+        let total_cells = self.width * self.height;
 
-                    mines.insert((rand_width, rand_height));
+        let exclude = if total_cells.saturating_sub(with_neighbors.len()) >= self.mine_count {
+            with_neighbors
+        } else if total_cells.saturating_sub(1) >= self.mine_count {
+            HashSet::from([p])
+        } else {
+            HashSet::new()
+        };
+
+        self.mines = Some(Self::create_mine_positions(
+            &mut self.rng,
+            self.width,
+            self.height,
+            self.mine_count,
+            &exclude,
+        ));
+        self.recompute_mine_counts();
+    }
+
+    // Caches each non-mine cell's adjacent mine count in `mine_counts`, so
+    // `count_mines` reads it in O(1) instead of re-walking neighbors for
+    // every cell on every render.
+    fn recompute_mine_counts(&mut self) {
+        let Some(mines) = self.mines.as_ref() else {
+            return;
+        };
+
+        self.mine_counts.clear();
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let pos = (x, y);
+                if mines.contains(&pos) {
+                    continue;
                 }
 
-                mines
-            },
-            open_cells: HashSet::new(),
-            flagged_cells: HashSet::new(),
+                let count = self
+                    .get_neighbors_pos(pos)
+                    .iter()
+                    .filter(|n| mines.contains(n))
+                    .count() as u8;
+                self.mine_counts.insert(pos, count);
+            }
         }
     }
 
+    // Reveals `p` and, if it has no adjacent mines, floods outwards through
+    // the connected region of zero-count cells, revealing their neighbors too.
     pub fn reveal_cell(&mut self, p: Position) -> RevealResult {
-        self.open_cells.insert(p);
+        if self.state != GameState::Playing {
+            return RevealResult::GameOver;
+        }
 
-        let is_mine = self.mines.contains(&p);
+        self.ensure_seeded(p);
 
-        if is_mine {
-            RevealResult::Mine
-        } else {
-            RevealResult::MineCount(self.count_mines(p))
+        if self.mines.as_ref().unwrap().contains(&p) {
+            self.open_cells.insert(p);
+            self.state = GameState::Lost;
+            return RevealResult::Mine;
         }
+
+        let mut queue = VecDeque::new();
+        queue.push_back(p);
+
+        while let Some(pos) = queue.pop_front() {
+            if !self.open_cells.insert(pos) {
+                continue;
+            }
+
+            if self.count_mines(pos) == 0 {
+                for neighbor in self.get_neighbors_pos(pos).iter() {
+                    let is_mine = self.mines.as_ref().unwrap().contains(&neighbor);
+                    if !self.open_cells.contains(&neighbor)
+                        && !self.flagged_cells.contains(&neighbor)
+                        && !is_mine
+                    {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+
+        self.check_win();
+
+        RevealResult::MineCount(self.count_mines(p))
     }
 
-    fn get_neighbors_pos(&self, (x, y): Position) -> impl Iterator<Item = Position> {
+    // Chords `p`: if `p` is revealed and its flagged-neighbor count equals
+    // its mine count, reveals every still-closed, unflagged neighbor. A
+    // wrongly-flagged neighbor can legitimately detonate a mine here.
+    pub fn chord(&mut self, p: Position) -> RevealResult {
+        if self.state != GameState::Playing {
+            return RevealResult::GameOver;
+        }
+
+        if !self.open_cells.contains(&p) {
+            return RevealResult::MineCount(self.count_mines(p));
+        }
+
+        let flagged_neighbors = self
+            .get_neighbors_pos(p)
+            .iter()
+            .filter(|n| self.flagged_cells.contains(n))
+            .count() as u8;
+
+        if flagged_neighbors != self.count_mines(p) {
+            return RevealResult::MineCount(self.count_mines(p));
+        }
+
+        let targets: Vec<Position> = self
+            .get_neighbors_pos(p)
+            .iter()
+            .filter(|n| !self.open_cells.contains(n) && !self.flagged_cells.contains(n))
+            .collect();
+
+        let mut result = RevealResult::MineCount(self.count_mines(p));
+        for neighbor in targets {
+            if self.state != GameState::Playing {
+                break;
+            }
+            result = self.reveal_cell(neighbor);
+        }
+        result
+    }
+
+    // A win is every non-mine cell opened, with no mine among them.
+    fn check_win(&mut self) {
+        let mine_count = self.mines.as_ref().map_or(0, HashSet::len);
+        if self.open_cells.len() == self.width * self.height - mine_count {
+            self.state = GameState::Won;
+        }
+    }
+
+    fn get_neighbors_pos(&self, (x, y): Position) -> NeighborPositions {
         let x_min = if x > 0 { x - 1 } else { x };
-        let x_max = if x >= self.width { x } else { x + 2 };
+        let x_max = (x + 2).min(self.width);
         let y_min = if y > 0 { y - 1 } else { y };
-        let y_max = if y >= self.height { y } else { y + 2 };
+        let y_max = (y + 2).min(self.height);
 
-        (x_min..x_max)
-            .flat_map(move |i| (y_min..y_max).map(move |j| (i, j)))
-            .filter(move |&pos| pos != (x, y))
+        let mut neighbors = NeighborPositions::new();
+        for i in x_min..x_max {
+            for j in y_min..y_max {
+                if (i, j) != (x, y) {
+                    neighbors.push((i, j));
+                }
+            }
+        }
+        neighbors
     }
-    // Only non-mines positions expected
+
+    // Reads the adjacent mine count cached in `mine_counts` by
+    // `recompute_mine_counts`. Only non-mine positions expected.
     fn count_mines(&self, p: Position) -> u8 {
-        self.get_neighbors_pos(p).fold(0, |acc, item| {
-            if self.mines.contains(&item) {
-                acc + 1
-            } else {
-                acc
-            }
-        })
+        self.mine_counts.get(&p).copied().unwrap_or(0)
     }
 
-    fn flag_cell(&mut self, p: Position) {
+    pub fn flag_cell(&mut self, p: Position) {
+        if self.state != GameState::Playing {
+            return;
+        }
+
         if !self.flagged_cells.insert(p) {
             self.flagged_cells.remove(&p);
         };
@@ -80,15 +439,15 @@ impl Minesweeper {
 
     fn format_cell(&self, p: Position) -> String {
         if self.open_cells.contains(&p) {
-            if self.mines.contains(&p) {
-                format!("*")
+            if self.mines.as_ref().is_some_and(|mines| mines.contains(&p)) {
+                "*".to_string()
             } else {
                 format!("{}", self.count_mines(p))
             }
         } else if self.flagged_cells.contains(&p) {
-            format!("f")
+            "f".to_string()
         } else {
-            format!("#")
+            "#".to_string()
         }
     }
 }
@@ -107,7 +466,7 @@ impl fmt::Display for Minesweeper {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Minesweeper, RevealResult};
+    use crate::{GameState, Minesweeper, Move, Replay, RevealResult};
 
     #[test]
     fn setup() {
@@ -134,23 +493,27 @@ mod tests {
 
     #[test]
     fn test_neighbour_pos() {
-        let ms = Minesweeper::new(2, 2, 0);
+        let ms = Minesweeper::new(4, 4, 0);
 
-        assert_eq!(3, ms.get_neighbors_pos((0, 0)).count());
-        assert_eq!(5, ms.get_neighbors_pos((0, 1)).count());
-        assert_eq!(8, ms.get_neighbors_pos((1, 1)).count());
+        assert_eq!(3, ms.get_neighbors_pos((0, 0)).iter().count());
+        assert_eq!(5, ms.get_neighbors_pos((0, 1)).iter().count());
+        assert_eq!(8, ms.get_neighbors_pos((1, 1)).iter().count());
     }
     #[test]
     fn test_counting_mines() {
         let empty_ms = Minesweeper::new(10, 10, 0);
         assert_eq!(0, empty_ms.count_mines((1, 1)));
 
+        // Requesting more mines (9) than the board has cells (4) clamps to
+        // a fully-mined board instead of spinning forever trying to place
+        // the rest, so every reveal is guaranteed to hit a mine.
         let mut full_ms = Minesweeper::new(2, 2, 9);
         match full_ms.reveal_cell((1, 1)) {
             RevealResult::Mine => {}
             RevealResult::MineCount(_c) => {
                 panic!("The Minefield is not full!");
             }
+            RevealResult::GameOver => panic!("game should still be in progress"),
         }
     }
 
@@ -163,4 +526,211 @@ mod tests {
         ms.flag_cell((1, 1));
         assert_eq!(ms.flagged_cells.len(), 0);
     }
+
+    #[test]
+    fn test_cascading_reveal_opens_connected_zero_region() {
+        // An empty board: every cell has zero adjacent mines, so revealing
+        // any single cell should flood the entire board open.
+        let mut ms = Minesweeper::new(4, 4, 0);
+        ms.reveal_cell((0, 0));
+        assert_eq!(ms.open_cells.len(), 16);
+    }
+
+    #[test]
+    fn test_cascading_reveal_stops_at_numbered_cells() {
+        // A 3x1 strip ending in a single mine: revealing the far end should
+        // only open up to (and including) the first numbered cell.
+        let mut ms = Minesweeper::new(3, 1, 0);
+        ms.mines = Some(std::collections::HashSet::from([(2, 0)]));
+        ms.recompute_mine_counts();
+
+        ms.reveal_cell((0, 0));
+
+        assert!(ms.open_cells.contains(&(0, 0)));
+        assert!(ms.open_cells.contains(&(1, 0)));
+        assert!(!ms.open_cells.contains(&(2, 0)));
+    }
+
+    #[test]
+    fn test_reveal_mine_loses_the_game() {
+        let mut ms = Minesweeper::new(2, 1, 0);
+        ms.mines = Some(std::collections::HashSet::from([(1, 0)]));
+        ms.recompute_mine_counts();
+
+        ms.reveal_cell((1, 0));
+
+        assert_eq!(ms.state(), GameState::Lost);
+    }
+
+    #[test]
+    fn test_revealing_every_safe_cell_wins_the_game() {
+        let mut ms = Minesweeper::new(2, 1, 0);
+        ms.mines = Some(std::collections::HashSet::from([(1, 0)]));
+        ms.recompute_mine_counts();
+
+        ms.reveal_cell((0, 0));
+
+        assert_eq!(ms.state(), GameState::Won);
+    }
+
+    #[test]
+    fn test_board_freezes_once_game_is_over() {
+        let mut ms = Minesweeper::new(2, 1, 0);
+        ms.mines = Some(std::collections::HashSet::from([(1, 0)]));
+        ms.recompute_mine_counts();
+
+        ms.reveal_cell((1, 0));
+        assert_eq!(ms.state(), GameState::Lost);
+
+        ms.flag_cell((0, 0));
+        assert!(!ms.flagged_cells.contains(&(0, 0)));
+
+        match ms.reveal_cell((0, 0)) {
+            RevealResult::GameOver => {}
+            _ => panic!("reveal_cell should no-op once the game is over"),
+        }
+        assert!(!ms.open_cells.contains(&(0, 0)));
+    }
+
+    // 4x2 board with a single mine at (3, 0). Revealing (0, 0) cascades
+    // everything open except the mine itself and the pocket cell (3, 1),
+    // which sits behind it and is only reachable by chording (2, 1).
+    fn chord_fixture() -> Minesweeper {
+        let mut ms = Minesweeper::new(4, 2, 0);
+        ms.mines = Some(std::collections::HashSet::from([(3, 0)]));
+        ms.recompute_mine_counts();
+        ms.reveal_cell((0, 0));
+        ms
+    }
+
+    #[test]
+    fn test_chord_reveals_unflagged_neighbors_when_satisfied() {
+        let mut ms = chord_fixture();
+        ms.flag_cell((3, 0));
+
+        ms.chord((2, 1));
+
+        assert!(ms.open_cells.contains(&(3, 1)));
+    }
+
+    #[test]
+    fn test_chord_with_wrong_flag_can_detonate_a_mine() {
+        // Flagging the safe pocket cell instead of the mine: chording
+        // should still open the real mine and lose the game.
+        let mut ms = chord_fixture();
+        ms.flag_cell((3, 1));
+
+        match ms.chord((2, 1)) {
+            RevealResult::Mine => {}
+            _ => panic!("chording with a misplaced flag should open the mine"),
+        }
+        assert_eq!(ms.state(), GameState::Lost);
+    }
+
+    #[test]
+    fn test_chord_is_a_noop_until_flag_count_matches() {
+        let mut ms = chord_fixture();
+
+        ms.chord((2, 1));
+
+        assert!(!ms.open_cells.contains(&(3, 0)));
+        assert!(!ms.open_cells.contains(&(3, 1)));
+    }
+
+    #[test]
+    fn test_first_click_is_never_a_mine() {
+        for _ in 0..99 {
+            let mut ms = Minesweeper::new(4, 4, 15);
+            match ms.reveal_cell((2, 2)) {
+                RevealResult::Mine => panic!("first click should never be a mine"),
+                RevealResult::MineCount(_) => {}
+                RevealResult::GameOver => panic!("game should still be in progress"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_mines_are_seeded_lazily() {
+        let ms = Minesweeper::new(4, 4, 5);
+        assert!(ms.mines.is_none());
+    }
+
+    #[test]
+    fn test_new_seeded_produces_a_reproducible_board() {
+        let mut a = Minesweeper::new_seeded(8, 8, 10, 42);
+        let mut b = Minesweeper::new_seeded(8, 8, 10, 42);
+
+        a.reveal_cell((0, 0));
+        b.reveal_cell((0, 0));
+
+        assert_eq!(a.mines, b.mines);
+    }
+
+    #[test]
+    fn test_replay_step_forward_and_back_reconstructs_board_state() {
+        let mut replay = Replay::new(4, 4, 0, 1);
+        replay.record(Move::ToggleFlag((0, 0)));
+        replay.record(Move::ToggleFlag((1, 1)));
+
+        assert_eq!(replay.len(), 2);
+        assert_eq!(replay.current_index(), 2);
+        assert!(replay.board().flagged_cells.contains(&(0, 0)));
+        assert!(replay.board().flagged_cells.contains(&(1, 1)));
+
+        replay.step_back();
+        assert_eq!(replay.current_index(), 1);
+        assert!(replay.board().flagged_cells.contains(&(0, 0)));
+        assert!(!replay.board().flagged_cells.contains(&(1, 1)));
+
+        replay.step_forward();
+        assert_eq!(replay.current_index(), 2);
+        assert!(replay.board().flagged_cells.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn test_replay_record_truncates_future_moves_when_branching() {
+        let mut replay = Replay::new(4, 4, 0, 1);
+        replay.record(Move::ToggleFlag((0, 0)));
+        replay.record(Move::ToggleFlag((1, 1)));
+        replay.step_back();
+
+        replay.record(Move::ToggleFlag((2, 2)));
+
+        assert_eq!(replay.len(), 2);
+        assert_eq!(replay.current_index(), 2);
+        assert!(replay.board().flagged_cells.contains(&(0, 0)));
+        assert!(!replay.board().flagged_cells.contains(&(1, 1)));
+        assert!(replay.board().flagged_cells.contains(&(2, 2)));
+    }
+
+    #[test]
+    fn test_replay_step_back_and_forward_are_no_ops_at_bounds() {
+        let mut replay = Replay::new(2, 2, 0, 1);
+        replay.step_back();
+        assert_eq!(replay.current_index(), 0);
+
+        replay.record(Move::ToggleFlag((0, 0)));
+        replay.step_forward();
+        assert_eq!(replay.current_index(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_to_json_and_from_json_round_trip_the_game() {
+        let mut ms = Minesweeper::new(3, 1, 0);
+        ms.mines = Some(std::collections::HashSet::from([(2, 0)]));
+        ms.recompute_mine_counts();
+        // Flag before revealing: the reveal wins the game, after which
+        // `flag_cell` is a no-op.
+        ms.flag_cell((2, 0));
+        ms.reveal_cell((0, 0));
+
+        let json = ms.to_json().expect("state should serialize");
+        let restored = Minesweeper::from_json(&json).expect("state should deserialize");
+
+        assert_eq!(restored.open_cells, ms.open_cells);
+        assert_eq!(restored.flagged_cells, ms.flagged_cells);
+        assert_eq!(restored.mines, ms.mines);
+        assert_eq!(restored.state(), ms.state());
+    }
 }
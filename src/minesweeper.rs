@@ -1,67 +1,289 @@
 use core::fmt;
-use rand::Rng;
-use std::collections::HashSet;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashSet, VecDeque};
 
 use wasm_bindgen::prelude::*;
 
 // TODO: Reduce size to 1 byte
 #[wasm_bindgen]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cell {
     state: CellState,
     value: CellValue,
 }
 
-#[wasm_bindgen]
-#[repr(u8)]
+// Not wasm-bindgen exposed directly (data-carrying enums aren't supported
+// across the boundary): `MineCount` caches the cell's adjacent mine count,
+// computed once per seed in `recompute_mine_counts` rather than re-walked
+// on every render.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CellValue {
-    Mine = 1,
-    MineCount = 0,
+    Mine,
+    MineCount(u8),
 }
 
 #[wasm_bindgen]
 #[repr(u8)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CellState {
     Closed = 0,
     Revealed = 2,
     Flagged = 3,
 }
 
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameState {
+    Playing = 0,
+    Won = 1,
+    Lost = 2,
+}
+
+// A single player action, as recorded by a `Replay`. Not wasm-bindgen
+// exposed directly (data-carrying enums aren't supported across the
+// boundary); `Replay` is plain Rust API for server-side/analysis use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Move {
+    Reveal(u32, u32),
+    ToggleFlag(u32, u32),
+    Chord(u32, u32),
+}
+
+// Records the moves played against a seeded board and reconstructs board
+// snapshots on demand, so a game can be stepped through move by move.
+//
+// Rewinding a `Minesweeper` isn't possible directly (its state lives in
+// a mutating `cells` buffer), so every step re-derives the board by
+// replaying moves `0..current_index` from the seed.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    width: u32,
+    height: u32,
+    mine_count: u32,
+    seed: u64,
+    moves: Vec<Move>,
+    current_index: usize,
+    board: Minesweeper,
+}
+
+impl Replay {
+    pub fn new(width: u32, height: u32, mine_count: u32, seed: u64) -> Replay {
+        Replay {
+            width,
+            height,
+            mine_count,
+            seed,
+            moves: Vec::new(),
+            current_index: 0,
+            board: Minesweeper::new_seeded(width, height, mine_count, seed),
+        }
+    }
+
+    // Records `mv` as the next move, discarding any moves after the
+    // current position, and steps forward onto it.
+    pub fn record(&mut self, mv: Move) -> &Minesweeper {
+        self.moves.truncate(self.current_index);
+        self.moves.push(mv);
+        self.step_forward()
+    }
+
+    pub fn step_forward(&mut self) -> &Minesweeper {
+        if self.current_index < self.moves.len() {
+            self.current_index += 1;
+            self.board = self.board_at(self.current_index);
+        }
+        &self.board
+    }
+
+    pub fn step_back(&mut self) -> &Minesweeper {
+        if self.current_index > 0 {
+            self.current_index -= 1;
+            self.board = self.board_at(self.current_index);
+        }
+        &self.board
+    }
+
+    pub fn board(&self) -> &Minesweeper {
+        &self.board
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current_index
+    }
+
+    // Replays moves `0..index` from the seed to rebuild the board as it
+    // stood at that point.
+    fn board_at(&self, index: usize) -> Minesweeper {
+        let mut board = Minesweeper::new_seeded(self.width, self.height, self.mine_count, self.seed);
+        for mv in &self.moves[..index] {
+            match *mv {
+                Move::Reveal(row, col) => {
+                    board.reveal_cell(row, col);
+                }
+                Move::ToggleFlag(row, col) => {
+                    board.toggle_flag(row, col);
+                }
+                Move::Chord(row, col) => {
+                    board.reveal_neighbors(row, col);
+                }
+            }
+        }
+        board
+    }
+}
+
+const MAX_NEIGHBORS: usize = 8;
+
+// A fixed-capacity, allocation-free set of up to 8 neighbor coordinates.
+// Neighbors used to be walked by an inline `flat_map`/`filter` closure
+// chain in `count_mines`, rebuilt on every call; a cell has at most 8
+// neighbors, so a stack array sized for that is enough.
+struct NeighborCoords {
+    coords: [(u32, u32); MAX_NEIGHBORS],
+    len: usize,
+}
+
+impl NeighborCoords {
+    fn new() -> NeighborCoords {
+        NeighborCoords {
+            coords: [(0, 0); MAX_NEIGHBORS],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, coord: (u32, u32)) {
+        self.coords[self.len] = coord;
+        self.len += 1;
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.coords[..self.len].iter().copied()
+    }
+}
+
 #[derive(Debug)]
 #[wasm_bindgen]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Minesweeper {
     width: u32,
     height: u32,
+    mine_count: u32,
+    // Mines aren't placed until the first `reveal_cell`, so that click can
+    // never lose.
+    seeded: bool,
+    state: GameState,
     cells: Vec<Cell>,
+    // Seeds `create_mine_positions`; replaying a `to_json`-saved board
+    // shouldn't require capturing RNG internals, so this is left out of
+    // the wire format and given a fresh one on `from_json`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip, default = "StdRng::from_entropy")
+    )]
+    rng: StdRng,
 }
 
 #[wasm_bindgen]
 impl Minesweeper {
     pub fn new(width: u32, height: u32, mine_count: u32) -> Minesweeper {
+        Self::with_rng(width, height, mine_count, StdRng::from_entropy())
+    }
+
+    // Like `new`, but drives mine placement from a seeded PRNG instead of
+    // system entropy, so the resulting board is reproducible from `seed`.
+    pub fn new_seeded(width: u32, height: u32, mine_count: u32, seed: u64) -> Minesweeper {
+        Self::with_rng(width, height, mine_count, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(width: u32, height: u32, mine_count: u32, rng: StdRng) -> Minesweeper {
         Minesweeper {
             width,
             height,
-            cells: {
-                let mut cells = Vec::new();
-                let mines = Minesweeper::create_mine_positions(width, height, mine_count);
-
-                for idx in 0..height * width {
-                    let cell_value = if mines.contains(&idx) {
-                        CellValue::Mine
-                    } else {
-                        CellValue::MineCount
-                    };
-
-                    cells.push(Cell {
-                        state: CellState::Closed,
-                        value: cell_value,
-                    });
+            mine_count,
+            seeded: false,
+            state: GameState::Playing,
+            cells: (0..height * width)
+                .map(|_| Cell {
+                    state: CellState::Closed,
+                    value: CellValue::MineCount(0),
+                })
+                .collect(),
+            rng,
+        }
+    }
+
+    // Places the mines on first use, excluding `row`/`col` and, if there is
+    // enough room left on the board, its neighbors too.
+    fn ensure_seeded(&mut self, row: u32, col: u32) {
+        if self.seeded {
+            return;
+        }
+        self.seeded = true;
+
+        let mut with_neighbors: HashSet<u32> = self
+            .get_neighbor_coords(row, col)
+            .iter()
+            .map(|(r, c)| self.get_index(r, c) as u32)
+            .collect();
+        with_neighbors.insert(self.get_index(row, col) as u32);
+
+        let total_cells = self.width * self.height;
+        let exclude = if total_cells.saturating_sub(with_neighbors.len() as u32) >= self.mine_count
+        {
+            with_neighbors
+        } else if total_cells.saturating_sub(1) >= self.mine_count {
+            HashSet::from([self.get_index(row, col) as u32])
+        } else {
+            HashSet::new()
+        };
+
+        let mines = Minesweeper::create_mine_positions(
+            &mut self.rng,
+            self.width,
+            self.height,
+            self.mine_count,
+            &exclude,
+        );
+        for idx in mines {
+            self.cells[idx as usize].value = CellValue::Mine;
+        }
+        self.recompute_mine_counts();
+    }
+
+    // Caches each non-mine cell's adjacent mine count in its `CellValue`,
+    // so `count_mines` and rendering read it in O(1) instead of re-walking
+    // neighbors for every cell on every render.
+    fn recompute_mine_counts(&mut self) {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = self.get_index(row, col);
+                if self.cells[idx].value == CellValue::Mine {
+                    continue;
                 }
 
-                cells
-            },
+                let count = self
+                    .get_neighbor_coords(row, col)
+                    .iter()
+                    .filter(|&(r, c)| matches!(self.get_cell(r, c), Some(cell) if cell.value == CellValue::Mine))
+                    .count() as u8;
+                self.cells[idx].value = CellValue::MineCount(count);
+            }
         }
     }
 
@@ -69,17 +291,77 @@ impl Minesweeper {
         self.to_string()
     }
 
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    // Snapshots the full game — dimensions, the flat cell buffer (mine
+    // layout, opened/flagged state) and game state — so it can be
+    // persisted and restored later.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Minesweeper state should always serialize")
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Minesweeper, JsValue> {
+        serde_json::from_str(json).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
     pub fn cells(&self) -> *const Cell {
         self.cells.as_ptr()
     }
 
+    // Reveals the cell at (row, col) and, if it has no adjacent mines,
+    // floods outwards through the connected region of zero-count cells.
     pub fn reveal_cell(&mut self, row: u32, col: u32) {
-        if let Some(cell) = self.get_cell_mut(row, col) {
-            cell.state = CellState::Revealed;
+        if !matches!(self.state, GameState::Playing) {
+            return;
+        }
+
+        self.ensure_seeded(row, col);
+
+        let mut queue = VecDeque::new();
+        queue.push_back((row, col));
+
+        while let Some((r, c)) = queue.pop_front() {
+            let is_mine = match self.get_cell(r, c) {
+                Some(cell)
+                    if matches!(cell.state, CellState::Revealed | CellState::Flagged) =>
+                {
+                    continue
+                }
+                Some(cell) => cell.value == CellValue::Mine,
+                None => continue,
+            };
+
+            if let Some(cell) = self.get_cell_mut(r, c) {
+                cell.state = CellState::Revealed;
+            }
+
+            if is_mine {
+                self.state = GameState::Lost;
+                continue;
+            }
+
+            if self.count_mines(r, c) == 0 {
+                for (nr, nc) in self.get_neighbor_coords(r, c).iter() {
+                    if matches!(self.get_cell(nr, nc), Some(c) if matches!(c.state, CellState::Closed))
+                    {
+                        queue.push_back((nr, nc));
+                    }
+                }
+            }
         }
+
+        self.check_win();
     }
 
     pub fn toggle_flag(&mut self, row: u32, column: u32) {
+        if !matches!(self.state, GameState::Playing) {
+            return;
+        }
+
         if let Some(c) = self.get_cell_mut(row, column) {
             match c.state {
                 CellState::Closed => c.state = CellState::Flagged,
@@ -89,24 +371,70 @@ impl Minesweeper {
         }
     }
 
+    // Chords (row, col): if that cell is revealed and its flagged-neighbor
+    // count equals its mine count, reveals every still-closed, unflagged
+    // neighbor. A wrongly-flagged neighbor can legitimately detonate a mine.
+    pub fn reveal_neighbors(&mut self, row: u32, col: u32) {
+        if !matches!(self.state, GameState::Playing) {
+            return;
+        }
+
+        if !matches!(self.get_cell(row, col), Some(cell) if matches!(cell.state, CellState::Revealed))
+        {
+            return;
+        }
+
+        let flagged_neighbors = self
+            .get_neighbor_coords(row, col)
+            .iter()
+            .filter(|&(r, c)| matches!(self.get_cell(r, c), Some(cell) if matches!(cell.state, CellState::Flagged)))
+            .count() as u8;
+
+        if flagged_neighbors != self.count_mines(row, col) {
+            return;
+        }
+
+        let targets: Vec<(u32, u32)> = self
+            .get_neighbor_coords(row, col)
+            .iter()
+            .filter(|&(r, c)| matches!(self.get_cell(r, c), Some(cell) if matches!(cell.state, CellState::Closed)))
+            .collect();
+
+        for (r, c) in targets {
+            if !matches!(self.state, GameState::Playing) {
+                break;
+            }
+            self.reveal_cell(r, c);
+        }
+    }
+
+    // A win is every non-mine cell revealed, with no mine among them.
+    fn check_win(&mut self) {
+        if matches!(self.state, GameState::Lost) {
+            return;
+        }
+
+        let revealed_safe = self
+            .cells
+            .iter()
+            .filter(|c| matches!(c.state, CellState::Revealed) && matches!(c.value, CellValue::MineCount(_)))
+            .count() as u32;
+
+        if revealed_safe == self.width * self.height - self.mine_count {
+            self.state = GameState::Won;
+        }
+    }
+
+    // Reads the adjacent mine count cached on the cell at (row, col) by
+    // `recompute_mine_counts`.
     pub fn count_mines(&self, row: u32, col: u32) -> u8 {
-        // Check bounds and get Range from -1 or 0 to 1
-        let row_min = if row > 0 { row - 1 } else { row };
-        let row_max = if row >= self.width - 1 { row } else { row + 1 };
-        let col_min = if col > 0 { col - 1 } else { col };
-        let col_max = if col >= self.height - 1 { col } else { col + 1 };
-        // Get relative positions and count mines
-        (row_min..=row_max)
-            .flat_map(move |i| (col_min..=col_max).map(move |j| (i, j)))
-            .filter(move |&pos| pos != (row, col))
-            .fold(0, |acc, (x, y)| {
-                if let Some(c) = self.get_cell(x, y) {
-                    if c.value == CellValue::Mine {
-                        return acc + 1;
-                    };
-                };
-                acc
-            })
+        match self.get_cell(row, col) {
+            Some(Cell {
+                value: CellValue::MineCount(n),
+                ..
+            }) => *n,
+            _ => 0,
+        }
     }
 
     fn get_index(&self, row: u32, column: u32) -> usize {
@@ -122,12 +450,52 @@ impl Minesweeper {
         self.cells.get_mut(idx)
     }
 
-    fn create_mine_positions(width: u32, height: u32, mine_count: u32) -> HashSet<u32> {
+    // Check bounds and get the (row, col) pairs surrounding (row, col)
+    fn get_neighbor_coords(&self, row: u32, col: u32) -> NeighborCoords {
+        let row_min = if row > 0 { row - 1 } else { row };
+        let row_max = if self.height == 0 || row >= self.height - 1 {
+            row
+        } else {
+            row + 1
+        };
+        let col_min = if col > 0 { col - 1 } else { col };
+        let col_max = if self.width == 0 || col >= self.width - 1 {
+            col
+        } else {
+            col + 1
+        };
+
+        let mut coords = NeighborCoords::new();
+        for i in row_min..=row_max {
+            for j in col_min..=col_max {
+                if (i, j) != (row, col) {
+                    coords.push((i, j));
+                }
+            }
+        }
+        coords
+    }
+
+    fn create_mine_positions(
+        rng: &mut impl Rng,
+        width: u32,
+        height: u32,
+        mine_count: u32,
+        exclude: &HashSet<u32>,
+    ) -> HashSet<u32> {
+        // Same bound as the native impl: cap `mine_count` at the flat
+        // indices still open after `exclude`, or the loop below spins
+        // forever hunting for mines that don't fit on the board.
+        let placeable = (width * height).saturating_sub(exclude.len() as u32);
+        let mine_count = mine_count.min(placeable);
+
         let mut mines = HashSet::with_capacity(mine_count as usize);
 
         while mines.len() < mine_count as usize {
-            let mine_pos = rand::thread_rng().gen_range(0..height * width);
-            mines.insert(mine_pos);
+            let mine_pos = rng.gen_range(0..height * width);
+            if !exclude.contains(&mine_pos) {
+                mines.insert(mine_pos);
+            }
         }
 
         mines
@@ -145,7 +513,7 @@ impl fmt::Display for Minesweeper {
                 let symbol = match cell.state {
                     CellState::Revealed => match cell.value {
                         CellValue::Mine => "*",
-                        CellValue::MineCount => match self.count_mines(row, col) {
+                        CellValue::MineCount(n) => match n {
                             1 => "1",
                             2 => "2",
                             3 => "3",
@@ -170,7 +538,7 @@ impl fmt::Display for Minesweeper {
 
 #[cfg(test)]
 mod tests {
-    use crate::minesweeper::{CellValue, Minesweeper};
+    use crate::minesweeper::{CellState, CellValue, GameState, Minesweeper, Move, Replay};
 
     #[test]
     fn setup() {
@@ -197,8 +565,11 @@ mod tests {
     #[test]
     fn test_mine_count() {
         for _ in 0..99 {
-            let ms = Minesweeper::new(20, 20, 20);
-            let ms_empty = Minesweeper::new(20, 20, 0);
+            // Mines aren't placed until the first reveal.
+            let mut ms = Minesweeper::new(20, 20, 20);
+            ms.reveal_cell(0, 0);
+            let mut ms_empty = Minesweeper::new(20, 20, 0);
+            ms_empty.reveal_cell(0, 0);
 
             assert_eq!(
                 ms.cells
@@ -217,5 +588,243 @@ mod tests {
             );
         }
     }
-}
 
+    #[test]
+    fn test_neighbor_coords_respect_each_axis_on_a_non_square_board() {
+        // width=3, height=5: a corner's neighbor set must stay within
+        // both axes' own bounds, not get the two swapped.
+        let ms = Minesweeper::new(3, 5, 0);
+
+        let corner: std::collections::HashSet<_> =
+            ms.get_neighbor_coords(0, 0).iter().collect();
+        assert_eq!(corner, std::collections::HashSet::from([(0, 1), (1, 0), (1, 1)]));
+
+        let far_corner: std::collections::HashSet<_> =
+            ms.get_neighbor_coords(4, 2).iter().collect();
+        assert_eq!(far_corner, std::collections::HashSet::from([(3, 1), (3, 2), (4, 1)]));
+    }
+
+    #[test]
+    fn test_first_click_is_never_a_mine() {
+        for _ in 0..99 {
+            let mut ms = Minesweeper::new(4, 4, 15);
+            ms.reveal_cell(2, 2);
+            assert!(matches!(ms.get_cell(2, 2).unwrap().value, CellValue::MineCount(_)));
+        }
+    }
+
+    #[test]
+    fn test_new_seeded_produces_a_reproducible_board() {
+        let mut a = Minesweeper::new_seeded(8, 8, 10, 42);
+        let mut b = Minesweeper::new_seeded(8, 8, 10, 42);
+
+        a.reveal_cell(0, 0);
+        b.reveal_cell(0, 0);
+
+        for i in 0..a.cells.len() {
+            assert_eq!(a.cells[i].value, b.cells[i].value);
+        }
+    }
+
+    #[test]
+    fn test_cascading_reveal_opens_connected_zero_region() {
+        // An empty board: every cell has zero adjacent mines, so revealing
+        // any single cell should flood the entire board open.
+        let mut ms = Minesweeper::new(4, 4, 0);
+        ms.reveal_cell(0, 0);
+        assert_eq!(
+            ms.cells
+                .iter()
+                .filter(|cell| matches!(cell.state, crate::minesweeper::CellState::Revealed))
+                .count(),
+            16
+        );
+    }
+
+    #[test]
+    fn test_reveal_mine_loses_the_game() {
+        // 2x2 board with a single mine at (0, 1), placed manually.
+        let mut ms = Minesweeper::new(2, 2, 1);
+        ms.ensure_seeded(1, 1);
+        ms.cells.iter_mut().for_each(|c| c.value = CellValue::MineCount(0));
+        ms.cells[1].value = CellValue::Mine;
+        ms.recompute_mine_counts();
+
+        ms.reveal_cell(0, 1);
+
+        assert_eq!(ms.state(), GameState::Lost);
+    }
+
+    #[test]
+    fn test_revealing_every_safe_cell_wins_the_game() {
+        let mut ms = Minesweeper::new(2, 2, 1);
+        ms.ensure_seeded(1, 1);
+        ms.cells.iter_mut().for_each(|c| c.value = CellValue::MineCount(0));
+        ms.cells[1].value = CellValue::Mine;
+        ms.recompute_mine_counts();
+
+        ms.reveal_cell(0, 0);
+        ms.reveal_cell(1, 0);
+        ms.reveal_cell(1, 1);
+
+        assert_eq!(ms.state(), GameState::Won);
+    }
+
+    // 2x2 board with a single mine at (0, 1). Revealing (0, 0) stops there
+    // (it borders the mine), leaving (1, 0) and (1, 1) closed but
+    // reachable by chording once the mine is flagged.
+    fn chord_fixture() -> Minesweeper {
+        let mut ms = Minesweeper::new(2, 2, 1);
+        ms.ensure_seeded(1, 1);
+        ms.cells.iter_mut().for_each(|c| c.value = CellValue::MineCount(0));
+        ms.cells[1].value = CellValue::Mine;
+        ms.recompute_mine_counts();
+        ms.reveal_cell(0, 0);
+        ms
+    }
+
+    #[test]
+    fn test_chord_reveals_unflagged_neighbors_when_satisfied() {
+        let mut ms = chord_fixture();
+        ms.toggle_flag(0, 1);
+
+        ms.reveal_neighbors(0, 0);
+
+        assert!(matches!(
+            ms.get_cell(1, 0).unwrap().state,
+            crate::minesweeper::CellState::Revealed
+        ));
+        assert!(matches!(
+            ms.get_cell(1, 1).unwrap().state,
+            crate::minesweeper::CellState::Revealed
+        ));
+    }
+
+    #[test]
+    fn test_chord_with_wrong_flag_can_detonate_a_mine() {
+        // Flagging the safe cell instead of the mine: chording should
+        // still open the real mine and lose the game.
+        let mut ms = chord_fixture();
+        ms.toggle_flag(1, 0);
+
+        ms.reveal_neighbors(0, 0);
+
+        assert_eq!(ms.state(), GameState::Lost);
+    }
+
+    #[test]
+    fn test_chord_is_a_noop_until_flag_count_matches() {
+        let mut ms = chord_fixture();
+
+        ms.reveal_neighbors(0, 0);
+
+        assert!(matches!(
+            ms.get_cell(1, 0).unwrap().state,
+            crate::minesweeper::CellState::Closed
+        ));
+        assert!(matches!(
+            ms.get_cell(1, 1).unwrap().state,
+            crate::minesweeper::CellState::Closed
+        ));
+    }
+
+    #[test]
+    fn test_board_freezes_once_game_is_over() {
+        let mut ms = Minesweeper::new(2, 2, 1);
+        ms.ensure_seeded(1, 1);
+        ms.cells.iter_mut().for_each(|c| c.value = CellValue::MineCount(0));
+        ms.cells[1].value = CellValue::Mine;
+        ms.recompute_mine_counts();
+
+        ms.reveal_cell(0, 1);
+        assert_eq!(ms.state(), GameState::Lost);
+
+        ms.toggle_flag(0, 0);
+        assert!(matches!(
+            ms.get_cell(0, 0).unwrap().state,
+            crate::minesweeper::CellState::Closed
+        ));
+
+        ms.reveal_cell(0, 0);
+        assert!(matches!(
+            ms.get_cell(0, 0).unwrap().state,
+            crate::minesweeper::CellState::Closed
+        ));
+    }
+
+    #[test]
+    fn test_replay_step_forward_and_back_reconstructs_board_state() {
+        let mut replay = Replay::new(4, 4, 0, 1);
+        replay.record(Move::ToggleFlag(0, 0));
+        replay.record(Move::ToggleFlag(1, 1));
+
+        assert_eq!(replay.len(), 2);
+        assert_eq!(replay.current_index(), 2);
+        assert!(matches!(replay.board().get_cell(0, 0).unwrap().state, CellState::Flagged));
+        assert!(matches!(replay.board().get_cell(1, 1).unwrap().state, CellState::Flagged));
+
+        replay.step_back();
+        assert_eq!(replay.current_index(), 1);
+        assert!(matches!(replay.board().get_cell(0, 0).unwrap().state, CellState::Flagged));
+        assert!(matches!(replay.board().get_cell(1, 1).unwrap().state, CellState::Closed));
+
+        replay.step_forward();
+        assert_eq!(replay.current_index(), 2);
+        assert!(matches!(replay.board().get_cell(1, 1).unwrap().state, CellState::Flagged));
+    }
+
+    #[test]
+    fn test_replay_record_truncates_future_moves_when_branching() {
+        let mut replay = Replay::new(4, 4, 0, 1);
+        replay.record(Move::ToggleFlag(0, 0));
+        replay.record(Move::ToggleFlag(1, 1));
+        replay.step_back();
+
+        replay.record(Move::ToggleFlag(2, 2));
+
+        assert_eq!(replay.len(), 2);
+        assert_eq!(replay.current_index(), 2);
+        assert!(matches!(replay.board().get_cell(0, 0).unwrap().state, CellState::Flagged));
+        assert!(matches!(replay.board().get_cell(1, 1).unwrap().state, CellState::Closed));
+        assert!(matches!(replay.board().get_cell(2, 2).unwrap().state, CellState::Flagged));
+    }
+
+    #[test]
+    fn test_replay_step_back_and_forward_are_no_ops_at_bounds() {
+        let mut replay = Replay::new(2, 2, 0, 1);
+        replay.step_back();
+        assert_eq!(replay.current_index(), 0);
+
+        replay.record(Move::ToggleFlag(0, 0));
+        replay.step_forward();
+        assert_eq!(replay.current_index(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_to_json_and_from_json_round_trip_the_game() {
+        let mut ms = Minesweeper::new(2, 2, 1);
+        ms.ensure_seeded(1, 1);
+        ms.cells.iter_mut().for_each(|c| c.value = CellValue::MineCount(0));
+        ms.cells[1].value = CellValue::Mine;
+        ms.recompute_mine_counts();
+        ms.reveal_cell(0, 0);
+        ms.toggle_flag(0, 1);
+
+        let json = ms.to_json();
+        let restored = Minesweeper::from_json(&json).expect("state should deserialize");
+
+        assert_eq!(restored.width, ms.width);
+        assert_eq!(restored.height, ms.height);
+        assert_eq!(restored.state(), ms.state());
+        for i in 0..ms.cells.len() {
+            assert!(matches!(
+                (restored.cells[i].state, ms.cells[i].state),
+                (CellState::Closed, CellState::Closed)
+                    | (CellState::Revealed, CellState::Revealed)
+                    | (CellState::Flagged, CellState::Flagged)
+            ));
+            assert_eq!(restored.cells[i].value, ms.cells[i].value);
+        }
+    }
+}